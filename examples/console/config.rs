@@ -0,0 +1,156 @@
+//! Declarative, multi-rule subscription configuration.
+//!
+//! Replaces the old hardcoded aircraft-type/area constants with a list of named
+//! [`Rule`]s, each combining an [`Area`], optional `AircraftType`/`AddressType`
+//! allow-lists and optional per-address allow/deny lists. Rules are combined into a
+//! single APRS-IS server-side `filter` expression (so the server does the coarse
+//! filtering), while [`Rule::matches`] applies the finer-grained predicates client-side
+//! in `read_messages`.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::sites::haversine_distance_m;
+use crate::{AddressType, AircraftType};
+
+/// An area a [`Rule`] is scoped to: either a circle or a lat/lon bounding box.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum Area {
+    /// Circle of `radius_km` around `(lat, lon)`.
+    Radius { lat: f64, lon: f64, radius_km: f64 },
+    /// Rectangle between the north-west and south-east corners.
+    BoundingBox {
+        lat_north: f64,
+        lon_west: f64,
+        lat_south: f64,
+        lon_east: f64,
+    },
+}
+
+impl Area {
+    fn contains(&self, lat: f64, lon: f64) -> bool {
+        match self {
+            Area::Radius { lat: c_lat, lon: c_lon, radius_km } => {
+                haversine_distance_m(*c_lat, *c_lon, lat, lon) <= radius_km * 1000.0
+            }
+            Area::BoundingBox { lat_north, lon_west, lat_south, lon_east } => {
+                lat <= *lat_north && lat >= *lat_south && lon >= *lon_west && lon <= *lon_east
+            }
+        }
+    }
+
+    /// Render as an APRS-IS server-side filter term.
+    ///
+    /// See <http://www.aprs-is.net/javAPRSFilter.aspx> for the `r/.../.../...` (range) and
+    /// `a/.../.../.../...`(area) filter syntax.
+    fn server_filter(&self) -> String {
+        match self {
+            Area::Radius { lat, lon, radius_km } => format!("r/{}/{}/{}", lat, lon, radius_km),
+            Area::BoundingBox { lat_north, lon_west, lat_south, lon_east } => {
+                format!("a/{}/{}/{}/{}", lat_north, lon_west, lat_south, lon_east)
+            }
+        }
+    }
+}
+
+/// A single named subscription rule.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    /// Label used for the rule's output stream, e.g. `"Schänis paragliders"`.
+    pub name: String,
+    pub area: Area,
+    /// Allowed aircraft types; empty means "any".
+    #[serde(default)]
+    pub aircraft_types: Vec<AircraftType>,
+    /// Allowed address types; empty means "any".
+    #[serde(default)]
+    pub address_types: Vec<AddressType>,
+    /// If non-empty, only these device addresses (hex, as in the `id...` field, matched
+    /// case-insensitively) match.
+    #[serde(default)]
+    pub allow_addresses: Vec<String>,
+    /// Device addresses that never match this rule, regardless of the other predicates.
+    #[serde(default)]
+    pub deny_addresses: Vec<String>,
+}
+
+impl Rule {
+    /// Whether a given sighting satisfies this rule's client-side predicates.
+    pub fn matches(
+        &self,
+        lat: f64,
+        lon: f64,
+        aircraft_type: &AircraftType,
+        address_type: &AddressType,
+        address: &str,
+    ) -> bool {
+        if !self.area.contains(lat, lon) {
+            return false;
+        }
+        if !self.aircraft_types.is_empty() && !self.aircraft_types.contains(aircraft_type) {
+            return false;
+        }
+        if !self.address_types.is_empty() && !self.address_types.contains(address_type) {
+            return false;
+        }
+        if !self.allow_addresses.is_empty()
+            && !self.allow_addresses.iter().any(|a| a.eq_ignore_ascii_case(address))
+        {
+            return false;
+        }
+        if self.deny_addresses.iter().any(|a| a.eq_ignore_ascii_case(address)) {
+            return false;
+        }
+        true
+    }
+}
+
+/// Top-level configuration: just a list of rules: one running instance can monitor
+/// several sites or aircraft classes at once.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub rules: Vec<Rule>,
+}
+
+impl Default for Config {
+    /// A single rule matching the original hardcoded behaviour: paragliders within 30 km
+    /// of Schänis, used when no `config.json` is found.
+    fn default() -> Self {
+        Config {
+            rules: vec![Rule {
+                name: "default".to_string(),
+                area: Area::Radius {
+                    lat: 47.217,
+                    lon: 8.804,
+                    radius_km: 30.0,
+                },
+                aircraft_types: vec![AircraftType::Paraglider],
+                address_types: Vec::new(),
+                allow_addresses: Vec::new(),
+                deny_addresses: Vec::new(),
+            }],
+        }
+    }
+}
+
+impl Config {
+    /// Combine every rule's area into the APRS-IS server-side `filter` line.
+    pub fn server_filter(&self) -> String {
+        self.rules
+            .iter()
+            .map(|rule| rule.area.server_filter())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Load the configuration from a JSON file.
+pub fn load_config(path: &Path) -> io::Result<Config> {
+    let text = fs::read_to_string(path)?;
+    serde_json::from_str(&text)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid config: {}", e)))
+}
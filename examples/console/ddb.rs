@@ -0,0 +1,120 @@
+//! Lookup of OGN device addresses against the Open Glider Network device database (DDB).
+//!
+//! The DDB is a public list of devices (FLARM/OGN trackers) registered by their owners,
+//! keyed by the device address also found in the `id...` field of the OGN comment. See
+//! <https://github.com/glidernet/ogn-ddb> for details on the format.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The default URL from which the DDB CSV export can be downloaded.
+pub const DDB_URL: &str = "https://ddb.glidernet.org/download/?t=1";
+
+/// Information about a single device, as registered in the DDB.
+#[derive(Debug, Clone)]
+pub struct DeviceInfo {
+    /// Aircraft registration (e.g. `HB-XYZ`).
+    pub registration: Option<String>,
+    /// Competition number / callsign.
+    pub competition_number: Option<String>,
+    /// Aircraft model as entered by the owner.
+    pub model: Option<String>,
+    /// Whether the owner allows the device to be tracked.
+    pub tracked: bool,
+    /// Whether the owner allows the aircraft to be identified by registration/CN.
+    pub identified: bool,
+}
+
+impl DeviceInfo {
+    /// Return the nicest human-readable label we have for this device, or `None` if the
+    /// owner has opted out of being identified by registration/CN/model.
+    pub fn label(&self) -> Option<&str> {
+        if !self.identified {
+            return None;
+        }
+        self.registration
+            .as_deref()
+            .or(self.competition_number.as_deref())
+            .or(self.model.as_deref())
+    }
+}
+
+/// Map from device address (uppercase hex, as found in the OGN comment) to [`DeviceInfo`].
+pub type Ddb = HashMap<String, DeviceInfo>;
+
+/// Parse a single quoted CSV field, stripping the surrounding `'...'` or `"..."` if present.
+fn unquote(field: &str) -> String {
+    let field = field.trim();
+    if field.len() >= 2 {
+        let bytes = field.as_bytes();
+        if (bytes[0] == b'\'' && bytes[field.len() - 1] == b'\'')
+            || (bytes[0] == b'"' && bytes[field.len() - 1] == b'"')
+        {
+            return field[1..field.len() - 1].to_string();
+        }
+    }
+    field.to_string()
+}
+
+/// Parse the DDB CSV export into a [`Ddb`].
+///
+/// Expected columns (comma separated, fields optionally quoted):
+/// `DEVICE_TYPE,DEVICE_ID,AIRCRAFT_MODEL,REGISTRATION,CN,TRACKED,IDENTIFIED`
+pub fn parse_ddb_csv(text: &str) -> Ddb {
+    let mut devices = Ddb::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() < 7 {
+            continue;
+        }
+        let address = unquote(fields[1]).to_uppercase();
+        if address.is_empty() {
+            continue;
+        }
+        let model = non_empty(unquote(fields[2]));
+        let registration = non_empty(unquote(fields[3]));
+        let competition_number = non_empty(unquote(fields[4]));
+        let tracked = unquote(fields[5]) == "Y";
+        let identified = unquote(fields[6]) == "Y";
+        devices.insert(
+            address,
+            DeviceInfo {
+                registration,
+                competition_number,
+                model,
+                tracked,
+                identified,
+            },
+        );
+    }
+    devices
+}
+
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+/// Fetch and parse the DDB from the given URL.
+pub async fn fetch_ddb(url: &str) -> io::Result<Ddb> {
+    let text = surf::get(url)
+        .recv_string()
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("DDB fetch failed: {}", e)))?;
+    Ok(parse_ddb_csv(&text))
+}
+
+/// Load the DDB from a local file, for offline use or as a fallback if the download fails.
+pub fn load_ddb_from_file(path: &Path) -> io::Result<Ddb> {
+    let text = fs::read_to_string(path)?;
+    Ok(parse_ddb_csv(&text))
+}
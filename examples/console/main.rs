@@ -0,0 +1,474 @@
+mod config;
+mod ddb;
+mod notifier;
+mod sites;
+mod tracker;
+mod transport;
+mod ws;
+
+use std::convert::TryFrom;
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Duration;
+
+use aprs_parser::{self, APRSData, Timestamp};
+use futures::io;
+use futures::prelude::*;
+use serde::Deserialize;
+use smol::Timer;
+
+use config::{Config, Rule};
+use ddb::Ddb;
+use notifier::{Notifier, StdoutNotifier, XmppNotifier};
+use tracker::{Event, EventDetector};
+use transport::Transport;
+use ws::SightingMessage;
+
+const APP_NAME: &str = env!("CARGO_PKG_NAME");
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Port 14580 is filtered
+const HOST: &str = "aprs.glidernet.org:14580";
+
+/// Default location of the rule configuration, overridable with `--config <path>`.
+const CONFIG_PATH: &str = "config.json";
+
+/// APRS-IS UDP submission/receive port, see <http://www.aprs-is.net/ClientUDP.aspx>.
+const UDP_HOST: &str = "aprs.glidernet.org:8080";
+
+/// How often to resend the login/filter line over UDP to keep the server-side filter alive.
+const UDP_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Fallback location for a locally cached copy of the DDB, used when the download fails.
+const DDB_FALLBACK_PATH: &str = "ddb.csv";
+
+/// Location of the named-sites configuration used for takeoff/landing detection.
+const SITES_PATH: &str = "sites.json";
+
+/// Bind address for the WebSocket broadcast server used by live map frontends.
+const WS_BIND_ADDR: &str = "0.0.0.0:9001";
+
+fn timestamp_to_str(ts: Timestamp) -> String {
+    match ts {
+        Timestamp::DDHHMM(d, h, m) => format!("{:02}/{:02}:{:02}", d, h, m),
+        Timestamp::HHMMSS(h, m, s) => format!("Today/{:02}:{:02}:{:02}", h, m, s),
+        Timestamp::Unsupported(val) => val.to_string(),
+    }
+}
+
+#[derive(Debug)]
+struct OgnComment {
+    address: String,
+    stealth_mode: bool,
+    no_tracking: bool,
+    aircraft_type: AircraftType,
+    address_type: AddressType,
+    /// Ground speed in km/h, parsed from the leading `course/speed` APRS field, if present.
+    ground_speed_kmh: Option<f64>,
+    /// Altitude in metres, parsed from the leading `/A=NNNNNN` APRS field, if present.
+    altitude_m: Option<f64>,
+    /// Climb rate in feet per minute, parsed from the OGN `[+-]NNNfpm` field, if present.
+    climb_fpm: Option<f64>,
+}
+
+/// Aircraft type.
+///
+/// See `AcftType` in FLARM DataPort Specification[1].
+///
+/// [1]: http://www.ediatec.ch/pdf/FLARM%20Data%20Port%20Specification%20v7.00.pdf
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub enum AircraftType {
+    /// Unknown type
+    Unknown,
+    /// Glider / motor glidre
+    Glider,
+    /// Tow / tug plane
+    TowPlane,
+    /// Helicopter / rotorcraft
+    Helicopter,
+    /// Skydiver
+    Skydiver,
+    /// Drop plane for skydivers
+    DropPlane,
+    /// Hang glider (hard)
+    Hangglider,
+    /// Paraglider (soft)
+    Paraglider,
+    /// Aircraft with reciprocating engine(s)
+    PoweredAircraft,
+    /// Aircraft with jet/turboprop engine(s)
+    JetAircraft,
+    /// Balloon
+    Balloon,
+    /// Airship
+    Airship,
+    /// Unmanned aerial vehicle (UAV)
+    Uav,
+    /// Static object
+    Static,
+}
+
+impl TryFrom<u8> for AircraftType {
+    type Error = ();
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            0x0 => Ok(AircraftType::Unknown),
+            0x1 => Ok(AircraftType::Glider),
+            0x2 => Ok(AircraftType::TowPlane),
+            0x3 => Ok(AircraftType::Helicopter),
+            0x4 => Ok(AircraftType::Skydiver),
+            0x5 => Ok(AircraftType::DropPlane),
+            0x6 => Ok(AircraftType::Hangglider),
+            0x7 => Ok(AircraftType::Paraglider),
+            0x8 => Ok(AircraftType::PoweredAircraft),
+            0x9 => Ok(AircraftType::JetAircraft),
+            0xa => Ok(AircraftType::Unknown),
+            0xb => Ok(AircraftType::Balloon),
+            0xc => Ok(AircraftType::Airship),
+            0xd => Ok(AircraftType::Uav),
+            0xe => Ok(AircraftType::Static),
+            0xf => Ok(AircraftType::Unknown),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for AircraftType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub enum AddressType {
+    Random,
+    Icao,
+    Flarm,
+    Ogn,
+}
+
+impl TryFrom<u8> for AddressType {
+    type Error = ();
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            0x0 => Ok(AddressType::Random),
+            0x1 => Ok(AddressType::Icao),
+            0x2 => Ok(AddressType::Flarm),
+            0x3 => Ok(AddressType::Ogn),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for AddressType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Self::Random => "Random",
+            Self::Icao => "ICAO",
+            Self::Flarm => "FLARM",
+            Self::Ogn => "OGN",
+        })
+    }
+}
+
+/// Parse the leading `course/speed/A=altitude` APRS field (e.g. `"088/009/A=001234"`).
+///
+/// `speed` is in knots and `altitude` in feet, per the APRS protocol spec; both are
+/// converted to metric units here.
+fn parse_course_speed_altitude(token: &str) -> (Option<f64>, Option<f64>) {
+    let mut parts = token.splitn(3, '/');
+    let _course = parts.next();
+    let speed_kmh = parts.next().and_then(|s| s.parse::<f64>().ok()).map(|knots| knots * 1.852);
+    let altitude_m = parts
+        .next()
+        .and_then(|s| s.strip_prefix("A="))
+        .and_then(|s| s.parse::<f64>().ok())
+        .map(|feet| feet * 0.3048);
+    (speed_kmh, altitude_m)
+}
+
+fn parse_ogn_comment(comment: &str) -> Option<OgnComment> {
+    // The course/speed/altitude extension is usually the first whitespace-separated token,
+    // but isn't guaranteed to be (some APRS parsers may have already consumed/relocated it
+    // out of the comment), so scan the whole comment for it rather than only checking the
+    // first token.
+    let tokens: Vec<&str> = comment.split(' ').collect();
+    let (ground_speed_kmh, altitude_m) = tokens
+        .iter()
+        .find(|t| t.len() >= 7 && t.as_bytes()[3] == b'/' && t.contains("/A="))
+        .map(|t| parse_course_speed_altitude(t))
+        .unwrap_or((None, None));
+    let mut tokens = tokens.into_iter();
+    let id = tokens.find(|x| x.starts_with("id"))?;
+    let flags = u8::from_str_radix(&id[2..4], 16).ok()?;
+    // Climb rate (e.g. `-019fpm`) follows the `id...` field; defaults to not present on
+    // stationary/ground stations.
+    let climb_fpm = tokens
+        .find(|t| t.ends_with("fpm"))
+        .and_then(|t| t.trim_end_matches("fpm").parse::<f64>().ok());
+    Some(OgnComment {
+        address: id[4..].to_string(),
+        stealth_mode: (flags & 0b10000000) > 0,
+        no_tracking: (flags & 0b01000000) > 0,
+        aircraft_type: AircraftType::try_from((flags & 0b00111100) >> 2).unwrap(),
+        address_type: AddressType::try_from(flags & 0b00000011).unwrap(),
+        ground_speed_kmh,
+        altitude_m,
+        climb_fpm,
+    })
+}
+
+fn format_event(event: &Event) -> String {
+    match event {
+        Event::Takeoff { site, address, aircraft, time } => {
+            format!("Takeoff: {} ({}) took off from {} at {}\n", aircraft, address, site, time)
+        }
+        Event::Landing { site, address, aircraft, time } => {
+            format!("Landing: {} ({}) landed at {} at {}\n", aircraft, address, site, time)
+        }
+    }
+}
+
+/// Read and process lines from the transport stream.
+///
+/// Matching sightings and flight events are routed through `notifier` (the operator-facing
+/// channel, e.g. an XMPP room). Raw protocol diagnostics - server comment lines, unparsed
+/// data, and parse errors - are logged to stdout instead: they're noise for whoever is
+/// subscribed to `notifier`, but useful when watching the process directly.
+async fn read_messages(
+    mut lines: Pin<Box<dyn Stream<Item = io::Result<String>> + Send>>,
+    ddb: &Ddb,
+    rules: &[Rule],
+    detector: &mut EventDetector,
+    notifier: &dyn Notifier,
+    ws_sender: &async_broadcast::Sender<SightingMessage>,
+) -> io::Result<()> {
+    // Handle every incoming line
+    while let Some(line) = lines.next().await {
+        let line = line?;
+        if line.starts_with("#") {
+            // Comment
+            println!("{}", line);
+        } else {
+            // APRS data
+            match aprs_parser::parse(&line) {
+                Ok(parsed) => match parsed.data {
+                    APRSData::Position(pos) => {
+                        let comment = match parse_ogn_comment(&pos.comment) {
+                            Some(comment) => comment,
+                            None => continue,
+                        };
+                        let matching_rules: Vec<&Rule> = rules
+                            .iter()
+                            .filter(|rule| {
+                                rule.matches(
+                                    pos.latitude as f64,
+                                    pos.longitude as f64,
+                                    &comment.aircraft_type,
+                                    &comment.address_type,
+                                    &comment.address,
+                                )
+                            })
+                            .collect();
+                        if matching_rules.is_empty() {
+                            continue;
+                        }
+                        // `parse_ddb_csv` keys the map by uppercased address, so normalize
+                        // the lookup key the same way rather than relying on the `id...`
+                        // field always being uppercase hex.
+                        let device = ddb.get(&comment.address.to_uppercase());
+                        if comment.no_tracking || device.map_or(false, |d| !d.tracked) {
+                            continue;
+                        }
+                        let aircraft = device
+                            .and_then(|d| d.label())
+                            .map(|label| label.to_string())
+                            .unwrap_or_else(|| format!("{} {}", comment.address_type, comment.address));
+                        let time = pos
+                            .timestamp
+                            .map(timestamp_to_str)
+                            .unwrap_or_else(|| "?".to_string());
+                        let log = format!(
+                            "{}: {:.6}/{:.6} ({} {} from {} to {} via {:?})",
+                            time,
+                            pos.latitude,
+                            pos.longitude,
+                            comment.aircraft_type,
+                            aircraft,
+                            parsed.from.call,
+                            parsed.to.call,
+                            parsed
+                                .via
+                                .iter()
+                                .map(|cs| cs.call.clone())
+                                .collect::<Vec<_>>(),
+                        );
+                        // One labeled notification per matching rule.
+                        for rule in &matching_rules {
+                            notifier.notify(&format!("[{}] {}", rule.name, log)).await?;
+                        }
+
+                        let sighting = SightingMessage {
+                            address: comment.address.clone(),
+                            registration: device.filter(|d| d.identified).and_then(|d| d.registration.clone()),
+                            aircraft_type: comment.aircraft_type.to_string(),
+                            latitude: pos.latitude as f64,
+                            longitude: pos.longitude as f64,
+                            altitude_m: comment.altitude_m,
+                            timestamp: time.clone(),
+                            ground_speed_kmh: comment.ground_speed_kmh,
+                            climb_fpm: comment.climb_fpm,
+                        };
+                        let _ = ws_sender.broadcast(sighting).await;
+
+                        if let (Some(altitude_m), Some(ground_speed_kmh)) =
+                            (comment.altitude_m, comment.ground_speed_kmh)
+                        {
+                            if let Some(event) = detector.update(
+                                &comment.address,
+                                &aircraft,
+                                pos.latitude as f64,
+                                pos.longitude as f64,
+                                altitude_m,
+                                ground_speed_kmh,
+                                comment.climb_fpm,
+                                &time,
+                            ) {
+                                notifier.notify(format_event(&event).trim_end()).await?;
+                            }
+                        }
+                    }
+                    APRSData::Unknown => println!("Unknown data: {}", line),
+                },
+                Err(e) => println!("Err: {}", e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Path to the rule configuration: `--config <path>` on the command line, falling back
+/// to [`CONFIG_PATH`].
+fn config_path() -> PathBuf {
+    let mut args = env::args();
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            if let Some(path) = args.next() {
+                return PathBuf::from(path);
+            }
+        }
+    }
+    PathBuf::from(CONFIG_PATH)
+}
+
+fn main() -> io::Result<()> {
+    smol::run(async {
+        // Load the rule configuration: named areas + aircraft/address filters, combined
+        // into the APRS-IS server-side filter below. Falls back to the legacy single-rule
+        // default (paragliders near Schänis) if no config file is found.
+        let config = config::load_config(&config_path()).unwrap_or_else(|e| {
+            eprintln!("Warning: Could not load config ({}), using default rule", e);
+            Config::default()
+        });
+        println!("Loaded {} rule(s)", config.rules.len());
+
+        // Load the OGN device database, so we can resolve addresses to registrations.
+        // Fall back to a local copy if the download fails (e.g. when offline).
+        let device_db = match ddb::fetch_ddb(ddb::DDB_URL).await {
+            Ok(db) => db,
+            Err(e) => {
+                eprintln!("Warning: Could not download DDB ({}), trying local fallback", e);
+                ddb::load_ddb_from_file(&PathBuf::from(DDB_FALLBACK_PATH)).unwrap_or_else(|e| {
+                    eprintln!("Warning: Could not load local DDB either ({})", e);
+                    Ddb::new()
+                })
+            }
+        };
+        println!("Loaded {} devices from DDB", device_db.len());
+
+        // Load the named sites used for takeoff/landing detection.
+        let sites = sites::load_sites(&PathBuf::from(SITES_PATH)).unwrap_or_else(|e| {
+            eprintln!("Warning: Could not load {} ({}), no sites configured", SITES_PATH, e);
+            Vec::new()
+        });
+        println!("Loaded {} sites", sites.len());
+        let mut detector = EventDetector::new(sites);
+
+        // Pick the notification backend: XMPP if configured via environment variables,
+        // stdout otherwise.
+        let notifier: Box<dyn Notifier> = match (
+            env::var("XMPP_JID"),
+            env::var("XMPP_PASSWORD"),
+            env::var("XMPP_ROOM"),
+            env::var("XMPP_NICK"),
+        ) {
+            (Ok(jid), Ok(password), Ok(room), Ok(nick)) => {
+                Box::new(XmppNotifier::connect(&jid, &password, &room, &nick).await?)
+            }
+            _ => Box::new(StdoutNotifier),
+        };
+
+        // Broadcast channel feeding the WebSocket server; overflow mode means a lagging
+        // client drops old messages instead of blocking the APRS-IS read loop below.
+        let (mut ws_sender, _ws_receiver) = async_broadcast::broadcast(1024);
+        ws_sender.set_overflow(true);
+        let ws_server_sender = ws_sender.clone();
+        smol::Task::spawn(async move {
+            if let Err(e) = ws::serve(WS_BIND_ADDR, ws_server_sender).await {
+                eprintln!("WebSocket server error: {}", e);
+            }
+        })
+        .detach();
+
+        // Connect to the server, either over TCP (the default) or UDP (set
+        // `APRS_TRANSPORT=udp`, useful for constrained/embedded receivers that only need
+        // to push or pull bursts rather than hold a connection open).
+        let use_udp = env::var("APRS_TRANSPORT").map(|v| v == "udp").unwrap_or(false);
+        let transport = if use_udp {
+            Transport::connect_udp(UDP_HOST).await?
+        } else {
+            Transport::connect_tcp(HOST).await?
+        };
+
+        // Login
+        let user = "bolOGNese"; // TODO
+        let pass = "-1"; // Password for receive-only clients according to APRS-IS docs
+        let filter = config.server_filter();
+        let auth_line = format!(
+            "user {} pass {} vers {} {} filter {}\r\n",
+            user, pass, APP_NAME, APP_VERSION, filter,
+        );
+        transport.send_line(&auth_line).await?;
+
+        // UDP has no persistent connection for the server to detect as alive, so keep
+        // resending the login/filter line periodically on a cloned socket.
+        if let Some(keepalive_transport) = transport.try_clone_for_keepalive()? {
+            let auth_line = auth_line.clone();
+            smol::Task::spawn(async move {
+                loop {
+                    Timer::after(UDP_KEEPALIVE_INTERVAL).await;
+                    if let Err(e) = keepalive_transport.send_line(&auth_line).await {
+                        eprintln!("Warning: UDP keepalive failed: {}", e);
+                    }
+                }
+            })
+            .detach();
+        }
+
+        // Process incoming stream
+        read_messages(
+            transport.into_lines(),
+            &device_db,
+            &config.rules,
+            &mut detector,
+            notifier.as_ref(),
+            &ws_sender,
+        )
+        .await?;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,32 @@
+//! Pluggable notification backends for sightings and takeoff/landing events.
+
+mod xmpp;
+
+use std::io;
+
+use async_trait::async_trait;
+
+pub use self::xmpp::XmppNotifier;
+
+/// Destination for human-readable notifications about sightings and flight events.
+///
+/// Not `Send`: the XMPP backend wraps `xmpp::Agent`, which holds `Rc` internals and can
+/// only ever run on a single thread. Every `Box<dyn Notifier>` in this crate lives inside
+/// the single future passed to `smol::run` in `main` and never crosses a `smol::Task::spawn`
+/// boundary, so this is not a practical restriction.
+#[async_trait(?Send)]
+pub trait Notifier {
+    /// Send a single notification message.
+    async fn notify(&self, message: &str) -> io::Result<()>;
+}
+
+/// Writes notifications to stdout, preserving the original console behaviour.
+pub struct StdoutNotifier;
+
+#[async_trait(?Send)]
+impl Notifier for StdoutNotifier {
+    async fn notify(&self, message: &str) -> io::Result<()> {
+        println!("{}", message);
+        Ok(())
+    }
+}
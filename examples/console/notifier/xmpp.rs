@@ -0,0 +1,62 @@
+//! XMPP multi-user chat (MUC) notifier backend.
+
+use std::io;
+
+use async_trait::async_trait;
+use futures::lock::Mutex;
+use jid::{BareJid, Jid};
+use xmpp::{Agent, ClientBuilder, ClientType, Event};
+use xmpp_parsers::message::MessageType;
+
+use super::Notifier;
+
+/// Sends notifications into an XMPP MUC room, e.g.
+/// `"Takeoff: HB-XYZ (DD1234) took off from Schänis at Today/14:32:00"`.
+pub struct XmppNotifier {
+    room: BareJid,
+    agent: Mutex<Agent>,
+}
+
+impl XmppNotifier {
+    /// Connect to the XMPP server as `jid`/`password` and join `room` under `nick`.
+    pub async fn connect(jid: &str, password: &str, room: &str, nick: &str) -> io::Result<Self> {
+        let room: BareJid = room
+            .parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Invalid room JID"))?;
+
+        let mut agent = ClientBuilder::new(jid, password)
+            .set_client(ClientType::Bot, env!("CARGO_PKG_NAME"))
+            .build()
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        // Wait until the connection is up before joining the room.
+        loop {
+            let events = agent
+                .wait_for_events()
+                .await
+                .ok_or_else(|| io::Error::other("XMPP connection closed before login"))?;
+            if events.iter().any(|event| matches!(event, Event::Online)) {
+                break;
+            }
+        }
+        agent
+            .join_room(room.clone(), Some(nick.to_string()), None, "en", "")
+            .await;
+
+        Ok(Self {
+            room,
+            agent: Mutex::new(agent),
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl Notifier for XmppNotifier {
+    async fn notify(&self, message: &str) -> io::Result<()> {
+        let mut agent = self.agent.lock().await;
+        agent
+            .send_message(Jid::Bare(self.room.clone()), MessageType::Groupchat, "en", message)
+            .await;
+        Ok(())
+    }
+}
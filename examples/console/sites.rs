@@ -0,0 +1,50 @@
+//! Named, geofenced flying sites (airfields, launch sites, ...).
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// A named location with a radius, used to decide whether a position belongs to a site.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Site {
+    /// Human-readable name, e.g. `"Schänis"`.
+    pub name: String,
+    /// Latitude in degrees.
+    pub lat: f64,
+    /// Longitude in degrees.
+    pub lon: f64,
+    /// Radius around `(lat, lon)` that counts as "at the site", in metres.
+    pub radius_m: f64,
+    /// Field elevation in metres, used as the ground reference for altitude-above-field.
+    #[serde(default)]
+    pub elevation_m: Option<f64>,
+}
+
+/// Mean Earth radius in metres, used for the haversine distance below.
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Great-circle distance between two lat/lon points, in metres.
+pub fn haversine_distance_m(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    let c = 2.0 * a.sqrt().asin();
+    EARTH_RADIUS_M * c
+}
+
+impl Site {
+    /// Whether the given position falls within this site's radius.
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        haversine_distance_m(self.lat, self.lon, lat, lon) <= self.radius_m
+    }
+}
+
+/// Load a list of sites from a `sites.json` file.
+pub fn load_sites(path: &Path) -> io::Result<Vec<Site>> {
+    let text = fs::read_to_string(path)?;
+    serde_json::from_str(&text)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid sites.json: {}", e)))
+}
@@ -0,0 +1,160 @@
+//! Takeoff/landing detection.
+//!
+//! Maintains a per-device ground/air state, classifying each sample on its own (no
+//! altitude or timestamp history is kept; only a count of consecutive samples that
+//! disagree with the current state, for hysteresis), and turns
+//! ground->air / air->ground transitions within a known [`Site`](crate::sites::Site)
+//! into [`Event`]s.
+
+use std::collections::HashMap;
+
+use crate::sites::Site;
+
+/// Number of consecutive samples on the other side of the threshold required before a
+/// state change is accepted. Avoids flapping from noisy altitude/speed readings.
+const HYSTERESIS_SAMPLES: u32 = 3;
+
+/// Minimum altitude above the site's field elevation, in metres, to be considered airborne.
+const AIRBORNE_ALT_THRESHOLD_M: f64 = 30.0;
+
+/// Minimum ground speed, in km/h, to be considered airborne when altitude-above-field is
+/// unavailable (no site elevation configured).
+const AIRBORNE_SPEED_THRESHOLD_KMH: f64 = 15.0;
+
+/// Minimum absolute climb rate, in feet per minute, that alone indicates the aircraft is
+/// airborne (catches e.g. a winch launch still within the site radius/elevation threshold).
+const AIRBORNE_CLIMB_THRESHOLD_FPM: f64 = 200.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlightState {
+    Ground,
+    Air,
+}
+
+/// A takeoff or landing, ready to be logged or forwarded to a [`Notifier`](crate::notifier::Notifier).
+#[derive(Debug, Clone)]
+pub enum Event {
+    Takeoff {
+        site: String,
+        address: String,
+        aircraft: String,
+        time: String,
+    },
+    Landing {
+        site: String,
+        address: String,
+        aircraft: String,
+        time: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct TrackState {
+    state: FlightState,
+    /// Number of consecutive samples seen on the side opposite to `state`.
+    pending_count: u32,
+    /// Name of the site the device was last confirmed inside of, if any.
+    site: Option<String>,
+}
+
+/// Stateful takeoff/landing detector, one instance per process.
+pub struct EventDetector {
+    sites: Vec<Site>,
+    tracks: HashMap<String, TrackState>,
+}
+
+impl EventDetector {
+    pub fn new(sites: Vec<Site>) -> Self {
+        Self {
+            sites,
+            tracks: HashMap::new(),
+        }
+    }
+
+    /// Find the site (if any) that the given position falls within.
+    fn site_at(&self, lat: f64, lon: f64) -> Option<&Site> {
+        self.sites.iter().find(|site| site.contains(lat, lon))
+    }
+
+    /// Classify a single sample as airborne or on-ground.
+    fn is_airborne(
+        &self,
+        site: Option<&Site>,
+        altitude_m: f64,
+        ground_speed_kmh: f64,
+        climb_fpm: Option<f64>,
+    ) -> bool {
+        let by_altitude_or_speed = match site.and_then(|s| s.elevation_m) {
+            Some(elevation_m) => (altitude_m - elevation_m) >= AIRBORNE_ALT_THRESHOLD_M,
+            None => ground_speed_kmh >= AIRBORNE_SPEED_THRESHOLD_KMH,
+        };
+        by_altitude_or_speed || climb_fpm.map_or(false, |c| c.abs() >= AIRBORNE_CLIMB_THRESHOLD_FPM)
+    }
+
+    /// Feed a new position sample for `address` into the detector, returning an [`Event`]
+    /// if this sample confirmed a ground<->air transition at a known site: a takeoff needs
+    /// the device to have last been on the ground inside a site, a landing needs the
+    /// confirming sample to be inside one. Other confirmed transitions return `None`.
+    pub fn update(
+        &mut self,
+        address: &str,
+        aircraft: &str,
+        lat: f64,
+        lon: f64,
+        altitude_m: f64,
+        ground_speed_kmh: f64,
+        climb_fpm: Option<f64>,
+        time: &str,
+    ) -> Option<Event> {
+        let site = self.site_at(lat, lon);
+        let airborne = self.is_airborne(site, altitude_m, ground_speed_kmh, climb_fpm);
+        // Resolve the name out of `site` now: `self.tracks.entry(...)` below needs `self`
+        // mutably, and `site` still borrows it immutably.
+        let site_name = site.map(|s| s.name.clone());
+        let sample_state = if airborne {
+            FlightState::Air
+        } else {
+            FlightState::Ground
+        };
+
+        let track = self.tracks.entry(address.to_string()).or_insert(TrackState {
+            state: FlightState::Ground,
+            pending_count: 0,
+            site: None,
+        });
+
+        if sample_state == track.state {
+            track.pending_count = 0;
+            if sample_state == FlightState::Ground {
+                track.site = site_name;
+            }
+            return None;
+        }
+
+        track.pending_count += 1;
+        if track.pending_count < HYSTERESIS_SAMPLES {
+            return None;
+        }
+
+        // Confirmed transition.
+        track.pending_count = 0;
+        let previous_site = track.site.clone();
+        track.state = sample_state;
+        track.site = site_name.clone();
+
+        match sample_state {
+            FlightState::Air => previous_site.map(|site_name| Event::Takeoff {
+                site: site_name,
+                address: address.to_string(),
+                aircraft: aircraft.to_string(),
+                time: time.to_string(),
+            }),
+            FlightState::Ground => site_name.map(|site_name| Event::Landing {
+                site: site_name,
+                address: address.to_string(),
+                aircraft: aircraft.to_string(),
+                time: time.to_string(),
+            }),
+        }
+    }
+}
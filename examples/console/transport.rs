@@ -0,0 +1,121 @@
+//! Transport abstraction over APRS-IS, so the line-handling in `read_messages` is shared
+//! between the TCP and UDP submission modes.
+//!
+//! See <http://www.aprs-is.net/ClientUDP.aspx> for the APRS-IS UDP protocol.
+
+use std::collections::VecDeque;
+use std::io;
+use std::io::Write as _;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::pin::Pin;
+
+use futures::io::{BufReader, Lines};
+use futures::prelude::*;
+use smol::Async;
+
+/// A connection to an APRS-IS server, either a persistent TCP stream or a UDP socket.
+pub enum Transport {
+    Tcp(Async<TcpStream>),
+    Udp {
+        socket: Async<UdpSocket>,
+        server: SocketAddr,
+    },
+}
+
+fn resolve(host: &str) -> io::Result<SocketAddr> {
+    host.to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("Could not resolve {}", host)))
+}
+
+impl Transport {
+    /// Connect to `host` (`host:port`) over TCP.
+    pub async fn connect_tcp(host: &str) -> io::Result<Self> {
+        let stream = Async::<TcpStream>::connect(resolve(host)?).await?;
+        if let Err(e) = stream.get_ref().set_nodelay(true) {
+            eprintln!("Warning: Could not set TCP_NODELAY on socket: {}", e);
+        }
+        println!("Connected to {} via TCP", stream.get_ref().peer_addr()?);
+        Ok(Transport::Tcp(stream))
+    }
+
+    /// "Connect" to `host` (`host:port`) over UDP: binds an ephemeral local socket, the
+    /// server address is kept around for sending the login line and keepalives.
+    pub async fn connect_udp(host: &str) -> io::Result<Self> {
+        let server = resolve(host)?;
+        let socket = Async::<UdpSocket>::bind(SocketAddr::from(([0, 0, 0, 0], 0)))?;
+        println!("Bound UDP socket {} for {}", socket.get_ref().local_addr()?, server);
+        Ok(Transport::Udp { socket, server })
+    }
+
+    /// Send a single line (e.g. the login/filter line, or a keepalive).
+    pub async fn send_line(&self, line: &str) -> io::Result<()> {
+        match self {
+            Transport::Tcp(stream) => {
+                stream.write_with(|mut s| s.write(line.as_bytes())).await?;
+                Ok(())
+            }
+            Transport::Udp { socket, server } => {
+                socket.send_to(line.as_bytes(), *server).await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Clone the transport for use by a background keepalive task. TCP connections don't
+    /// need one (the server treats the open connection as alive), so this only returns
+    /// `Some` for UDP.
+    pub fn try_clone_for_keepalive(&self) -> io::Result<Option<Transport>> {
+        match self {
+            Transport::Tcp(_) => Ok(None),
+            Transport::Udp { socket, server } => {
+                let cloned = socket.get_ref().try_clone()?;
+                Ok(Some(Transport::Udp {
+                    socket: Async::new(cloned)?,
+                    server: *server,
+                }))
+            }
+        }
+    }
+
+    /// Turn the transport into a stream of received lines, shared by `read_messages`
+    /// regardless of which transport produced them.
+    pub fn into_lines(self) -> Pin<Box<dyn Stream<Item = io::Result<String>> + Send>> {
+        match self {
+            Transport::Tcp(stream) => Box::pin(TcpLines(BufReader::new(stream).lines())),
+            Transport::Udp { socket, .. } => Box::pin(udp_line_stream(socket)),
+        }
+    }
+}
+
+/// Thin wrapper so [`futures::io::Lines`] (which isn't `Send` by itself on some executors)
+/// lines up with the boxed `Stream` type used for both transports.
+struct TcpLines(Lines<BufReader<Async<TcpStream>>>);
+
+impl Stream for TcpLines {
+    type Item = io::Result<String>;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        Pin::new(&mut this.0).poll_next(cx)
+    }
+}
+
+/// A UDP datagram can contain several newline-separated APRS lines; split and emit them one
+/// at a time so the rest of the pipeline never has to know about datagram framing.
+fn udp_line_stream(socket: Async<UdpSocket>) -> impl Stream<Item = io::Result<String>> {
+    stream::unfold((socket, VecDeque::new()), |(socket, mut pending)| async move {
+        loop {
+            if let Some(line) = pending.pop_front() {
+                return Some((Ok(line), (socket, pending)));
+            }
+            let mut buf = [0u8; 2048];
+            match socket.recv_from(&mut buf).await {
+                Ok((n, _)) => {
+                    let text = String::from_utf8_lossy(&buf[..n]).into_owned();
+                    pending = text.lines().map(str::to_string).collect();
+                }
+                Err(e) => return Some((Err(e), (socket, pending))),
+            }
+        }
+    })
+}
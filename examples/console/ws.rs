@@ -0,0 +1,72 @@
+//! WebSocket broadcast server: streams parsed positions to connected browsers as JSON, so a
+//! live map frontend can consume the feed directly.
+
+use std::io;
+use std::net::{TcpListener, TcpStream};
+
+use async_tungstenite::tungstenite::Message;
+use futures::prelude::*;
+use serde::Serialize;
+use smol::Async;
+
+/// A single parsed, filtered position, ready to be pushed to connected clients.
+#[derive(Debug, Clone, Serialize)]
+pub struct SightingMessage {
+    pub address: String,
+    pub registration: Option<String>,
+    pub aircraft_type: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_m: Option<f64>,
+    pub timestamp: String,
+    pub ground_speed_kmh: Option<f64>,
+    pub climb_fpm: Option<f64>,
+}
+
+/// Accept WebSocket connections on `bind_addr` and forward every message broadcast via
+/// `sender` to each connected client as a JSON text frame.
+///
+/// Each client gets its own receiver tapped off `sender`; the channel is configured to drop
+/// the oldest pending message for a lagging client rather than block the sender, so a slow
+/// browser never holds up the upstream APRS-IS read loop.
+pub async fn serve(bind_addr: &str, sender: async_broadcast::Sender<SightingMessage>) -> io::Result<()> {
+    let addr: std::net::SocketAddr = bind_addr
+        .parse()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Invalid bind address"))?;
+    let listener = Async::<TcpListener>::bind(addr)?;
+    println!("WebSocket server listening on {}", bind_addr);
+
+    loop {
+        let (stream, peer_addr) = listener.accept().await?;
+        let receiver = sender.new_receiver();
+        smol::Task::spawn(async move {
+            if let Err(e) = handle_client(stream, receiver).await {
+                eprintln!("WebSocket client {} disconnected: {}", peer_addr, e);
+            }
+        })
+        .detach();
+    }
+}
+
+async fn handle_client(
+    stream: Async<TcpStream>,
+    mut receiver: async_broadcast::Receiver<SightingMessage>,
+) -> io::Result<()> {
+    let mut ws = async_tungstenite::accept_async(stream)
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+    loop {
+        let sighting = match receiver.recv().await {
+            Ok(sighting) => sighting,
+            // Fell behind and missed the oldest messages: carry on with the next one.
+            Err(async_broadcast::RecvError::Overflowed(_)) => continue,
+            Err(async_broadcast::RecvError::Closed) => break,
+        };
+        let json = serde_json::to_string(&sighting)?;
+        ws.send(Message::Text(json))
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+    }
+    Ok(())
+}